@@ -1,7 +1,12 @@
 use std::{fmt, str};
 
+use nom::IResult;
+
 use common::{column_identifier_no_alias, integer_literal, opt_multispace, Literal};
 use column::Column;
+use case::{case_expression, CaseExpression};
+use user_function::{function_call, FunctionCall};
+use span::Span;
 
 #[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum ArithmeticOperator {
@@ -9,12 +14,25 @@ pub enum ArithmeticOperator {
     Subtract,
     Multiply,
     Divide,
+    Modulo,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
 }
 
 #[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum ArithmeticBase {
     Column(Column),
     Scalar(Literal),
+    Expression(Box<ArithmeticExpression>),
+    Case(Box<CaseExpression>),
+    FunctionCall(FunctionCall),
+    /// Unary `-` applied to another base, e.g. the `-5` in `-5 + x`. Kept distinct from
+    /// `Expression(Subtract, Scalar(0), ...)` so downstream consumers (and `Display`) see an
+    /// actual negation rather than a synthesized `0 - x` subtraction.
+    Negate(Box<ArithmeticBase>),
 }
 
 #[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -22,18 +40,30 @@ pub struct ArithmeticExpression {
     pub op: ArithmeticOperator,
     pub left: ArithmeticBase,
     pub right: ArithmeticBase,
+    /// Where this expression was found, as byte offsets from the start of the input given to
+    /// `arithmetic_expression`; inside a `CASE` branch or a function-call argument, the offsets
+    /// count from the start of that branch or argument instead. Ignored by `PartialEq`
+    /// and `Hash` (see `Span`), so it never needs to be threaded through hand-built test
+    /// expectations.
+    pub span: Span,
 }
 
 impl ArithmeticExpression {
-    pub fn new(
+    pub fn new(op: ArithmeticOperator, left: ArithmeticBase, right: ArithmeticBase) -> Self {
+        Self::new_with_span(op, left, right, Span::default())
+    }
+
+    pub fn new_with_span(
         op: ArithmeticOperator,
         left: ArithmeticBase,
         right: ArithmeticBase,
+        span: Span,
     ) -> Self {
         Self {
             op: op,
             left: left,
             right: right,
+            span: span,
         }
     }
 }
@@ -45,6 +75,12 @@ impl fmt::Display for ArithmeticOperator {
             ArithmeticOperator::Subtract => write!(f, "-"),
             ArithmeticOperator::Multiply => write!(f, "*"),
             ArithmeticOperator::Divide => write!(f, "/"),
+            ArithmeticOperator::Modulo => write!(f, "%"),
+            ArithmeticOperator::BitwiseAnd => write!(f, "&"),
+            ArithmeticOperator::BitwiseOr => write!(f, "|"),
+            ArithmeticOperator::BitwiseXor => write!(f, "^"),
+            ArithmeticOperator::ShiftLeft => write!(f, "<<"),
+            ArithmeticOperator::ShiftRight => write!(f, ">>"),
         }
     }
 }
@@ -54,24 +90,142 @@ impl fmt::Display for ArithmeticBase {
         match *self {
             ArithmeticBase::Column(ref col) => write!(f, "{}", col),
             ArithmeticBase::Scalar(ref lit) => write!(f, "{}", lit.to_string()),
+            ArithmeticBase::Expression(ref expr) => write!(f, "{}", expr),
+            ArithmeticBase::Case(ref case) => write!(f, "{}", case),
+            ArithmeticBase::FunctionCall(ref call) => write!(f, "{}", call),
+            ArithmeticBase::Negate(ref base) => {
+                // Unary `-` binds to a primary, so a nested binary `Expression` needs parens
+                // to round-trip (`-(a + b)`, not the re-parsed-as-binary `-a + b`). A nested
+                // `Negate` needs a separating space for the same reason `FunctionCall` args
+                // need commas: two bare hyphens in a row (`--5`) is SQL's line-comment marker,
+                // so it'd silently truncate the rest of the line instead of round-tripping.
+                match **base {
+                    ArithmeticBase::Expression(ref e) => write!(f, "-({})", e),
+                    ArithmeticBase::Negate(_) => write!(f, "- {}", base),
+                    _ => write!(f, "-{}", base),
+                }
+            }
         }
     }
 }
 
+/// The binding power of each operator: operators with a higher precedence bind more tightly,
+/// e.g. in `1 + 2 * 3`, `*`'s precedence of 6 beats `+`'s precedence of 5, so it's parsed as
+/// `1 + (2 * 3)`. The bitwise/shift operators sit below `+`/`-` (but above comparison, which
+/// lives outside this grammar), `%` is tied with `*`/`/`, and, matching MySQL/Postgres, the
+/// bitwise/shift operators aren't all tied with each other: `<<`/`>>` binds tighter than `&`,
+/// which binds tighter than `^`, which binds tighter than `|` (e.g. `a | b & c` parses as
+/// `a | (b & c)`).
+fn arithmetic_precedence(op: &ArithmeticOperator) -> u8 {
+    match *op {
+        ArithmeticOperator::Multiply | ArithmeticOperator::Divide | ArithmeticOperator::Modulo => 6,
+        ArithmeticOperator::Add | ArithmeticOperator::Subtract => 5,
+        ArithmeticOperator::ShiftLeft | ArithmeticOperator::ShiftRight => 4,
+        ArithmeticOperator::BitwiseAnd => 3,
+        ArithmeticOperator::BitwiseXor => 2,
+        ArithmeticOperator::BitwiseOr => 1,
+    }
+}
+
+/// `-`, `/`, `%`, `<<` and `>>` aren't associative, so a right child with the same
+/// precedence as its parent still needs parenthesizing, e.g. `a - (b - c) != (a - b) - c`.
+fn arithmetic_is_non_associative(op: &ArithmeticOperator) -> bool {
+    match *op {
+        ArithmeticOperator::Subtract
+        | ArithmeticOperator::Divide
+        | ArithmeticOperator::Modulo
+        | ArithmeticOperator::ShiftLeft
+        | ArithmeticOperator::ShiftRight => true,
+        ArithmeticOperator::Add
+        | ArithmeticOperator::Multiply
+        | ArithmeticOperator::BitwiseAnd
+        | ArithmeticOperator::BitwiseOr
+        | ArithmeticOperator::BitwiseXor => false,
+    }
+}
+
+impl ArithmeticExpression {
+    fn fmt_operand(
+        base: &ArithmeticBase,
+        parent_op: &ArithmeticOperator,
+        is_right: bool,
+        verbose: bool,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        let child = match *base {
+            ArithmeticBase::Expression(ref e) => e,
+            _ => return write!(f, "{}", base),
+        };
+
+        let parent_prec = arithmetic_precedence(parent_op);
+        let child_prec = arithmetic_precedence(&child.op);
+        // A right child tied with its parent's precedence still needs parens unless it's the
+        // exact same operator as an associative parent (`a + (b + c)` keeps its value without
+        // them, though it re-parses as `(a + b) + c`): either the parent doesn't associate (`a - (b - c)`), or the child is a
+        // different operator than the parent (`a * (b % c)` - left-to-right folding of the
+        // unparenthesized string would instead compute `(a * b) % c`).
+        let needs_parens = verbose
+            || child_prec < parent_prec
+            || (child_prec == parent_prec
+                && is_right
+                && (arithmetic_is_non_associative(parent_op) || child.op != *parent_op));
+
+        if needs_parens {
+            write!(f, "(")?;
+            child.fmt_inner(f, verbose)?;
+            write!(f, ")")
+        } else {
+            child.fmt_inner(f, verbose)
+        }
+    }
+
+    fn fmt_inner(&self, f: &mut fmt::Formatter, verbose: bool) -> fmt::Result {
+        Self::fmt_operand(&self.left, &self.op, false, verbose, f)?;
+        write!(f, " {} ", self.op)?;
+        Self::fmt_operand(&self.right, &self.op, true, verbose, f)
+    }
+
+    /// Wraps this expression so that every binary node is parenthesized, regardless of
+    /// whether the parentheses are needed to preserve semantics. Useful for handing the
+    /// expression off to another engine whose precedence rules might differ from ours.
+    pub fn verbose(&self) -> VerboseArithmeticExpression {
+        VerboseArithmeticExpression(self)
+    }
+}
+
+/// A wrapper that renders an `ArithmeticExpression` with full (not just minimal)
+/// parenthesization; see `ArithmeticExpression::verbose`.
+pub struct VerboseArithmeticExpression<'a>(&'a ArithmeticExpression);
+
+impl<'a> fmt::Display for VerboseArithmeticExpression<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        self.0.fmt_inner(f, true)?;
+        write!(f, ")")
+    }
+}
+
 impl fmt::Display for ArithmeticExpression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} {}", self.left, self.op, self.right)
+        self.fmt_inner(f, false)
     }
 }
 
 /// Parse standard math operators.
-/// TODO(malte): this doesn't currently observe operator precedence.
 named!(pub arithmetic_operator<&[u8], ArithmeticOperator>,
     alt_complete!(
-          map!(tag!("+"), |_| ArithmeticOperator::Add)
+          // multi-character tokens must be tried before their single-character prefixes,
+          // or `<<`/`>>` would mis-tokenize as a stray `<`/`>` followed by leftover input.
+          map!(tag!("<<"), |_| ArithmeticOperator::ShiftLeft)
+        | map!(tag!(">>"), |_| ArithmeticOperator::ShiftRight)
+        | map!(tag!("+"), |_| ArithmeticOperator::Add)
         | map!(tag!("-"), |_| ArithmeticOperator::Subtract)
         | map!(tag!("*"), |_| ArithmeticOperator::Multiply)
         | map!(tag!("/"), |_| ArithmeticOperator::Divide)
+        | map!(tag!("%"), |_| ArithmeticOperator::Modulo)
+        | map!(tag!("&"), |_| ArithmeticOperator::BitwiseAnd)
+        | map!(tag!("|"), |_| ArithmeticOperator::BitwiseOr)
+        | map!(tag!("^"), |_| ArithmeticOperator::BitwiseXor)
     )
 );
 
@@ -83,22 +237,122 @@ named!(pub arithmetic_base<&[u8], ArithmeticBase>,
     )
 );
 
-/// Parse simple arithmetic expressions combining literals, and columns and literals.
-/// TODO(malte): this doesn't currently support nested expressions.
-named!(pub arithmetic_expression<&[u8], ArithmeticExpression>,
-    complete!(do_parse!(
-        left: arithmetic_base >>
-        opt_multispace >>
-        op: arithmetic_operator >>
-        opt_multispace >>
-        right: arithmetic_base >>
-        (ArithmeticExpression {
-            op: op,
-            left: left,
-            right: right,
-        })
-    ))
-);
+/// A primary in the precedence-climbing grammar below: either a plain `arithmetic_base`, a
+/// unary `+`/`-` applied to another primary, or a fully parenthesized sub-expression (which
+/// resets precedence climbing back to `min_prec = 0`). `origin` is the input the enclosing
+/// `arithmetic_expression` call started from, threaded through so parenthesized and negated
+/// sub-expressions compute spans relative to the same anchor; `CASE` branches and
+/// function-call arguments are parsed with their own start as the anchor.
+fn arithmetic_primary<'a>(i: &'a [u8], origin: &'a [u8]) -> IResult<&'a [u8], ArithmeticBase> {
+    alt_complete!(
+        i,
+          map!(case_expression, |c| ArithmeticBase::Case(Box::new(c)))
+        | delimited!(
+              pair!(tag!("("), opt_multispace),
+              apply!(arithmetic_climb, origin, 0),
+              pair!(opt_multispace, tag!(")"))
+          )
+        | do_parse!(
+              tag!("-") >>
+              opt_multispace >>
+              rhs: apply!(arithmetic_primary, origin) >>
+              (ArithmeticBase::Negate(Box::new(rhs)))
+          )
+        | preceded!(pair!(tag!("+"), opt_multispace), apply!(arithmetic_primary, origin))
+        | call!(arithmetic_base_or_function_call)
+    )
+}
+
+/// `arithmetic_base`'s column parser recognizes known aggregate calls like `MAX(foo)` and
+/// consumes the whole `name(args)` as part of the column name, but for any other identifier it
+/// only consumes the bare name, leaving a trailing `(args)` user-defined-function call
+/// unconsumed. Try `arithmetic_base` first, so aggregates keep taking priority over
+/// `function_call`; if it stops right before a `(`, it must have matched a non-aggregate
+/// function name instead of a column, so retry the whole primary as a `function_call`.
+fn arithmetic_base_or_function_call(i: &[u8]) -> IResult<&[u8], ArithmeticBase> {
+    match arithmetic_base(i) {
+        IResult::Done(rest, base) => {
+            let after_ws = match opt_multispace(rest) {
+                IResult::Done(r, _) => r,
+                _ => rest,
+            };
+            if after_ws.first() == Some(&b'(') {
+                match map!(i, function_call, |c| ArithmeticBase::FunctionCall(c)) {
+                    done @ IResult::Done(..) => done,
+                    _ => IResult::Done(rest, base),
+                }
+            } else {
+                IResult::Done(rest, base)
+            }
+        }
+        IResult::Error(e) => match map!(i, function_call, |c| ArithmeticBase::FunctionCall(c)) {
+            done @ IResult::Done(..) => done,
+            _ => IResult::Error(e),
+        },
+        IResult::Incomplete(n) => IResult::Incomplete(n),
+    }
+}
+
+/// Precedence-climbing core: parses a primary, then repeatedly folds in any following
+/// operator whose precedence is `>= min_prec`, recursing on the right-hand operand with
+/// `min_prec` raised to `op_prec + 1` so that operators bind left-associatively.
+pub fn arithmetic_climb<'a>(
+    i: &'a [u8],
+    origin: &'a [u8],
+    min_prec: u8,
+) -> IResult<&'a [u8], ArithmeticBase> {
+    let start = i;
+    let (mut rest, mut left) = try_parse!(i, apply!(arithmetic_primary, origin));
+
+    loop {
+        let after_ws = match opt_multispace(rest) {
+            IResult::Done(r, _) => r,
+            _ => rest,
+        };
+
+        match arithmetic_operator(after_ws) {
+            IResult::Done(after_op, op) => {
+                let prec = arithmetic_precedence(&op);
+                if prec < min_prec {
+                    break;
+                }
+
+                let after_op_ws = match opt_multispace(after_op) {
+                    IResult::Done(r, _) => r,
+                    _ => after_op,
+                };
+
+                match arithmetic_climb(after_op_ws, origin, prec + 1) {
+                    IResult::Done(after_right, right) => {
+                        let span = Span::from_offsets(origin, start, after_right);
+                        left = ArithmeticBase::Expression(Box::new(
+                            ArithmeticExpression::new_with_span(op, left, right, span),
+                        ));
+                        rest = after_right;
+                    }
+                    _ => break,
+                }
+            }
+            _ => break,
+        }
+    }
+
+    IResult::Done(rest, left)
+}
+
+/// Parse arithmetic expressions combining literals and columns, observing operator
+/// precedence and supporting arbitrarily nested (parenthesized) sub-expressions.
+pub fn arithmetic_expression(i: &[u8]) -> IResult<&[u8], ArithmeticExpression> {
+    match arithmetic_climb(i, i, 0) {
+        IResult::Done(rest, ArithmeticBase::Expression(boxed)) => IResult::Done(rest, *boxed),
+        IResult::Done(_, _) => IResult::Error(error_position!(
+            ::nom::ErrorKind::Custom(0),
+            i
+        )),
+        IResult::Error(e) => IResult::Error(e),
+        IResult::Incomplete(n) => IResult::Incomplete(n),
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -203,4 +457,278 @@ mod tests {
             assert_eq!(expected_strings[i], format!("{}", e));
         }
     }
+
+    #[test]
+    fn it_displays_minimal_parentheses() {
+        // 5 + 2 * 3 doesn't need parens around the right-hand `2 * 3` since `*` already
+        // binds tighter than `+`.
+        let res = arithmetic_expression("5 + 2 * 3".as_bytes()).unwrap().1;
+        assert_eq!(format!("{}", res), "5 + 2 * 3");
+
+        // (a + b) * c does need parens: without them it'd mean a + (b * c).
+        let res = arithmetic_expression("(a + b) * c".as_bytes()).unwrap().1;
+        assert_eq!(format!("{}", res), "(a + b) * c");
+
+        // a - (b - c) needs parens since `-` isn't associative.
+        let res = arithmetic_expression("a - (b - c)".as_bytes()).unwrap().1;
+        assert_eq!(format!("{}", res), "a - (b - c)");
+
+        // whereas (a - b) - c, being left-associative, doesn't.
+        let res = arithmetic_expression("(a - b) - c".as_bytes()).unwrap().1;
+        assert_eq!(format!("{}", res), "a - b - c");
+    }
+
+    #[test]
+    fn it_displays_verbose_parentheses() {
+        let res = arithmetic_expression("5 + 2 * 3".as_bytes()).unwrap().1;
+        assert_eq!(format!("{}", res.verbose()), "(5 + (2 * 3))");
+    }
+
+    #[test]
+    fn it_round_trips_through_display() {
+        let exprs = [
+            "5 + 2 * 3",
+            "(a + b) * c",
+            "a - (b - c)",
+            "(a - b) - c",
+            "a * b / c",
+            "a + b + c",
+        ];
+
+        for e in exprs.iter() {
+            let parsed = arithmetic_expression(e.as_bytes()).unwrap().1;
+            let displayed = format!("{}", parsed);
+            let reparsed = arithmetic_expression(displayed.as_bytes()).unwrap().1;
+            assert_eq!(parsed, reparsed);
+        }
+    }
+
+    #[test]
+    fn it_parenthesizes_differing_operators_at_the_same_precedence_tier() {
+        // `a * (b % c)` pairs two distinct operators tied in precedence; without parens the
+        // unparenthesized string would fold left-to-right into a different tree (`(a * b) % c`)
+        // and, for most inputs, a different value. `a & (b << c)` pairs operators on adjacent
+        // tiers: `<<` binds tighter than `&`, so it prints without parens and still round-trips.
+        let exprs = ["a * (b % c)", "a & (b << c)"];
+        for e in exprs.iter() {
+            let parsed = arithmetic_expression(e.as_bytes()).unwrap().1;
+            let displayed = format!("{}", parsed);
+            let reparsed = arithmetic_expression(displayed.as_bytes()).unwrap().1;
+            assert_eq!(parsed, reparsed);
+        }
+    }
+
+    #[test]
+    fn it_parses_precedence_in_arithmetic_expressions() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Scalar;
+        use super::ArithmeticBase::Expression as ABExpression;
+
+        // 5 + 2 * 3 == 5 + (2 * 3), not (5 + 2) * 3
+        let res = arithmetic_expression("5 + 2 * 3".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            ArithmeticExpression::new(
+                Add,
+                Scalar(5.into()),
+                ABExpression(Box::new(ArithmeticExpression::new(
+                    Multiply,
+                    Scalar(2.into()),
+                    Scalar(3.into()),
+                ))),
+            )
+        );
+    }
+
+    #[test]
+    fn it_parses_nested_parenthesized_arithmetic_expressions() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Column as ABColumn;
+        use super::ArithmeticBase::Expression as ABExpression;
+
+        // (a + b) * c
+        let res = arithmetic_expression("(a + b) * c".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            ArithmeticExpression::new(
+                Multiply,
+                ABExpression(Box::new(ArithmeticExpression::new(
+                    Add,
+                    ABColumn("a".into()),
+                    ABColumn("b".into()),
+                ))),
+                ABColumn("c".into()),
+            )
+        );
+    }
+
+    #[test]
+    fn it_parses_left_associative_arithmetic_expressions() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Scalar;
+        use super::ArithmeticBase::Expression as ABExpression;
+
+        // 10 - 2 - 3 == (10 - 2) - 3, not 10 - (2 - 3)
+        let res = arithmetic_expression("10 - 2 - 3".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            ArithmeticExpression::new(
+                Subtract,
+                ABExpression(Box::new(ArithmeticExpression::new(
+                    Subtract,
+                    Scalar(10.into()),
+                    Scalar(2.into()),
+                ))),
+                Scalar(3.into()),
+            )
+        );
+    }
+
+    #[test]
+    fn it_captures_spans_without_affecting_equality() {
+        let res = arithmetic_expression("5 + 42".as_bytes()).unwrap().1;
+        // `Span`'s `PartialEq` always returns `true` (see below), so compare the raw
+        // offsets directly rather than via `assert_eq!(res.span, Span::new(0, 6))`, which
+        // would pass even if span computation were broken.
+        assert_eq!(res.span.start, 0);
+        assert_eq!(res.span.end, 6);
+
+        // Hand-built expressions (with a default/unknown span) still compare equal to
+        // parsed ones, since spans are ignored by `PartialEq`.
+        use super::ArithmeticOperator::Add;
+        use super::ArithmeticBase::Scalar;
+        assert_eq!(
+            res,
+            ArithmeticExpression::new(Add, Scalar(5.into()), Scalar(42.into()))
+        );
+    }
+
+    #[test]
+    fn it_parses_modulo_and_bitwise_operators() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Scalar;
+
+        let cases = [
+            ("5 % 2", Modulo),
+            ("5 & 2", BitwiseAnd),
+            ("5 | 2", BitwiseOr),
+            ("5 ^ 2", BitwiseXor),
+            ("5 << 2", ShiftLeft),
+            ("5 >> 2", ShiftRight),
+        ];
+
+        for &(q, ref op) in cases.iter() {
+            let res = arithmetic_expression(q.as_bytes());
+            assert!(res.is_done());
+            assert_eq!(
+                res.unwrap().1,
+                ArithmeticExpression::new(op.clone(), Scalar(5.into()), Scalar(2.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn it_gives_modulo_the_same_precedence_as_multiply() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Scalar;
+        use super::ArithmeticBase::Expression as ABExpression;
+
+        // 5 + 2 % 3 == 5 + (2 % 3), since % binds as tightly as * and /.
+        let res = arithmetic_expression("5 + 2 % 3".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            ArithmeticExpression::new(
+                Add,
+                Scalar(5.into()),
+                ABExpression(Box::new(ArithmeticExpression::new(
+                    Modulo,
+                    Scalar(2.into()),
+                    Scalar(3.into()),
+                ))),
+            )
+        );
+    }
+
+    #[test]
+    fn it_gives_bitwise_shift_lower_precedence_than_addition() {
+        use super::ArithmeticOperator::*;
+        use super::ArithmeticBase::Scalar;
+        use super::ArithmeticBase::Expression as ABExpression;
+
+        // 1 + 2 << 3 == (1 + 2) << 3
+        let res = arithmetic_expression("1 + 2 << 3".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            ArithmeticExpression::new(
+                ShiftLeft,
+                ABExpression(Box::new(ArithmeticExpression::new(
+                    Add,
+                    Scalar(1.into()),
+                    Scalar(2.into()),
+                ))),
+                Scalar(3.into()),
+            )
+        );
+    }
+
+    #[test]
+    fn it_round_trips_bitwise_operators_through_display() {
+        let exprs = ["5 % 2", "5 & 2 | 3", "1 + 2 << 3"];
+        for e in exprs.iter() {
+            let parsed = arithmetic_expression(e.as_bytes()).unwrap().1;
+            let displayed = format!("{}", parsed);
+            let reparsed = arithmetic_expression(displayed.as_bytes()).unwrap().1;
+            assert_eq!(parsed, reparsed);
+        }
+    }
+
+    #[test]
+    fn it_parses_user_defined_function_calls_as_operands() {
+        use super::ArithmeticBase::{FunctionCall as ABFunctionCall, Scalar};
+
+        // Drive a non-aggregate call through the full arithmetic grammar (not just
+        // `function_call` in isolation), proving `add(1)` actually reaches that arm instead of
+        // being swallowed as a bare `Column("add")` with a dangling `(1)`.
+        let res = arithmetic_expression("add(1) + 2".as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(parsed.op, ArithmeticOperator::Add);
+        assert_eq!(
+            parsed.left,
+            ABFunctionCall(FunctionCall {
+                name: String::from("add"),
+                arguments: vec![Scalar(1.into())],
+            })
+        );
+        assert_eq!(parsed.right, Scalar(2.into()));
+    }
+
+    #[test]
+    fn it_parses_unary_minus_as_negation() {
+        use super::ArithmeticBase::{Negate, Scalar};
+
+        let res = arithmetic_expression("-5 + foo".as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(parsed.op, ArithmeticOperator::Add);
+        assert_eq!(parsed.left, Negate(Box::new(Scalar(5.into()))));
+    }
+
+    #[test]
+    fn it_round_trips_unary_minus_through_display() {
+        let exprs = ["-5 + foo", "a - -b", "-(a + b) * c", "x + - -5"];
+        for e in exprs.iter() {
+            let parsed = arithmetic_expression(e.as_bytes()).unwrap().1;
+            let displayed = format!("{}", parsed);
+            let reparsed = arithmetic_expression(displayed.as_bytes()).unwrap().1;
+            assert_eq!(parsed, reparsed);
+        }
+    }
 }
@@ -0,0 +1,293 @@
+use std::fmt;
+use std::str;
+
+use nom::{multispace, IResult};
+
+use common::{literal, opt_multispace};
+use condition::{condition_expr, ConditionBase, ConditionExpression, ConditionTree};
+use condition::Operator;
+
+use arithmetic::{arithmetic_base, arithmetic_climb, ArithmeticBase};
+
+/// A single `WHEN <condition> THEN <result>` branch of a `CASE` expression.
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CaseWhenBranch {
+    pub condition: ConditionExpression,
+    pub result: ArithmeticBase,
+}
+
+/// `CASE WHEN <condition> THEN <result> [...] [ELSE <result>] END`.
+///
+/// The "simple" form, `CASE <operand> WHEN <value> THEN <result> ...`, is desugared at parse
+/// time into the searched form above by rewriting each `WHEN <value>` into
+/// `WHEN <operand> = <value>`, so only one representation needs to be carried around.
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CaseExpression {
+    pub when_clauses: Vec<CaseWhenBranch>,
+    pub else_clause: Option<Box<ArithmeticBase>>,
+}
+
+impl fmt::Display for CaseWhenBranch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WHEN {} THEN {}", self.condition, self.result)
+    }
+}
+
+impl fmt::Display for CaseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CASE")?;
+        for branch in &self.when_clauses {
+            write!(f, " {}", branch)?;
+        }
+        if let Some(ref else_clause) = self.else_clause {
+            write!(f, " ELSE {}", else_clause)?;
+        }
+        write!(f, " END")
+    }
+}
+
+/// Only `arithmetic_base` (a column or an integer literal) can appear as a simple-`CASE` operand or
+/// value, so this conversion is always exact.
+fn arithmetic_base_to_condition_base(base: ArithmeticBase) -> ConditionBase {
+    match base {
+        ArithmeticBase::Column(c) => ConditionBase::Field(c),
+        ArithmeticBase::Scalar(l) => ConditionBase::Literal(l),
+        _ => unreachable!("simple CASE operand/value must be a column or a literal"),
+    }
+}
+
+fn equality_condition(operand: &ArithmeticBase, value: ArithmeticBase) -> ConditionExpression {
+    ConditionExpression::ComparisonOp(ConditionTree {
+        left: Box::new(ConditionExpression::Base(arithmetic_base_to_condition_base(
+            operand.clone(),
+        ))),
+        right: Box::new(ConditionExpression::Base(arithmetic_base_to_condition_base(
+            value,
+        ))),
+        operator: Operator::Equal,
+    })
+}
+
+/// A `THEN`/`ELSE` result: an arithmetic expression or operand, or any other literal (such as
+/// a string) that `arithmetic_base` doesn't accept.
+fn case_result(i: &[u8]) -> IResult<&[u8], ArithmeticBase> {
+    alt_complete!(
+        i,
+          call!(|i| arithmetic_climb(i, i, 0))
+        | map!(literal, ArithmeticBase::Scalar)
+    )
+}
+
+named!(searched_when_branch<&[u8], CaseWhenBranch>,
+    complete!(do_parse!(
+        tag_no_case!("when") >>
+        multispace >>
+        condition: condition_expr >>
+        opt_multispace >>
+        tag_no_case!("then") >>
+        multispace >>
+        result: case_result >>
+        (CaseWhenBranch { condition: condition, result: result })
+    ))
+);
+
+fn simple_when_branch<'a>(
+    i: &'a [u8],
+    operand: &ArithmeticBase,
+) -> IResult<&'a [u8], CaseWhenBranch> {
+    do_parse!(
+        i,
+        tag_no_case!("when") >>
+        multispace >>
+        // The value compared against `operand` is folded into an equality condition, so (like
+        // the operand itself) it's limited to what `arithmetic_base` accepts, a column or an
+        // integer literal, not arbitrary arithmetic sub-expressions.
+        value: arithmetic_base >>
+        opt_multispace >>
+        tag_no_case!("then") >>
+        multispace >>
+        result: case_result >>
+        (CaseWhenBranch {
+            condition: equality_condition(operand, value),
+            result: result,
+        })
+    )
+}
+
+/// True if the upcoming input is the `WHEN` keyword, i.e. `"when"` (any case) followed by
+/// something other than an identifier character. Used to decide whether `CASE` is being used
+/// in searched form (`CASE WHEN ...`) before attempting to parse a simple-form operand, since
+/// nothing about `arithmetic_base`/`sql_identifier` reserves `WHEN` as a keyword.
+fn at_when(i: &[u8]) -> bool {
+    match tag_no_case!(i, "when") as IResult<&[u8], &[u8]> {
+        IResult::Done(rest, _) => match rest.first() {
+            Some(&c) => !(c.is_ascii_alphanumeric() || c == b'_'),
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+pub fn case_expression(i: &[u8]) -> IResult<&[u8], CaseExpression> {
+    let (i, _) = try_parse!(i, tag_no_case!("case"));
+    let (i, _) = try_parse!(i, multispace);
+
+    let (i, operand) = if at_when(i) {
+        (i, None)
+    } else {
+        let (rest, op) = try_parse!(i, terminated!(arithmetic_base, multispace));
+        (rest, Some(op))
+    };
+
+    let (i, when_clauses) = try_parse!(
+        i,
+        many1!(terminated!(
+            call!(|i| match operand {
+                Some(ref op) => simple_when_branch(i, op),
+                None => searched_when_branch(i),
+            }),
+            opt_multispace
+        ))
+    );
+
+    let (i, else_clause) = try_parse!(
+        i,
+        opt!(complete!(do_parse!(
+            tag_no_case!("else") >>
+            multispace >>
+            result: case_result >>
+            opt_multispace >>
+            (result)
+        )))
+    );
+
+    let (i, _) = try_parse!(i, tag_no_case!("end"));
+
+    IResult::Done(
+        i,
+        CaseExpression {
+            when_clauses: when_clauses,
+            else_clause: else_clause.map(Box::new),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arithmetic::ArithmeticBase::{Column as ABColumn, Scalar};
+    use condition::{ConditionBase, ConditionExpression, ConditionTree, Operator};
+
+    #[test]
+    fn it_parses_searched_case_expressions() {
+        let qstr = "CASE WHEN x > 5 THEN 1 ELSE 0 END";
+        let res = case_expression(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let expected = CaseExpression {
+            when_clauses: vec![
+                CaseWhenBranch {
+                    condition: ConditionExpression::ComparisonOp(ConditionTree {
+                        left: Box::new(ConditionExpression::Base(ConditionBase::Field(
+                            "x".into(),
+                        ))),
+                        right: Box::new(ConditionExpression::Base(ConditionBase::Literal(
+                            5.into(),
+                        ))),
+                        operator: Operator::Greater,
+                    }),
+                    result: Scalar(1.into()),
+                },
+            ],
+            else_clause: Some(Box::new(Scalar(0.into()))),
+        };
+
+        assert_eq!(res.unwrap().1, expected);
+    }
+
+    #[test]
+    fn it_parses_simple_case_expressions() {
+        let qstr = "CASE status WHEN 1 THEN 'active' WHEN 2 THEN 'inactive' END";
+        let res = case_expression(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(parsed.when_clauses.len(), 2);
+        assert_eq!(
+            parsed.when_clauses[0].condition,
+            ConditionExpression::ComparisonOp(ConditionTree {
+                left: Box::new(ConditionExpression::Base(ConditionBase::Field(
+                    "status".into(),
+                ))),
+                right: Box::new(ConditionExpression::Base(ConditionBase::Literal(
+                    1.into(),
+                ))),
+                operator: Operator::Equal,
+            })
+        );
+        assert_eq!(parsed.else_clause, None);
+    }
+
+    #[test]
+    fn it_round_trips_case_expressions_through_display() {
+        let qstr = "CASE WHEN x > 5 THEN 1 ELSE 0 END";
+        let parsed = case_expression(qstr.as_bytes()).unwrap().1;
+        let displayed = format!("{}", parsed);
+        let reparsed = case_expression(displayed.as_bytes()).unwrap().1;
+        assert_eq!(parsed, reparsed);
+    }
+
+    #[test]
+    fn it_disambiguates_simple_case_operands_named_like_when() {
+        // "whenever" must be parsed as a simple-CASE operand, not mistaken for the `WHEN`
+        // keyword just because it starts with the same four letters.
+        let qstr = "CASE whenever WHEN 1 THEN 'a' ELSE 'b' END";
+        let res = case_expression(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(
+            parsed.when_clauses[0].condition,
+            ConditionExpression::ComparisonOp(ConditionTree {
+                left: Box::new(ConditionExpression::Base(ConditionBase::Field(
+                    "whenever".into(),
+                ))),
+                right: Box::new(ConditionExpression::Base(ConditionBase::Literal(
+                    1.into(),
+                ))),
+                operator: Operator::Equal,
+            })
+        );
+    }
+
+    #[test]
+    fn it_parses_arithmetic_results_in_then_and_else() {
+        use arithmetic::ArithmeticBase::Expression as ABExpression;
+
+        let qstr = "CASE WHEN x > 5 THEN a + 1 ELSE b * 2 END";
+        let res = case_expression(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert!(match parsed.when_clauses[0].result {
+            ABExpression(_) => true,
+            _ => false,
+        });
+        assert!(match parsed.else_clause {
+            Some(ref e) => match **e {
+                ABExpression(_) => true,
+                _ => false,
+            },
+            None => false,
+        });
+    }
+
+    #[test]
+    fn it_allows_nested_case_in_arithmetic() {
+        use arithmetic::arithmetic_expression;
+
+        let qstr = "1 + CASE WHEN x > 5 THEN 1 ELSE 0 END";
+        let res = arithmetic_expression(qstr.as_bytes());
+        assert!(res.is_done());
+    }
+}
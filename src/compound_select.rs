@@ -1,9 +1,13 @@
-use nom::multispace;
-use std::str;
+use nom::{multispace, IResult};
+use std::fmt;
 
 use common::opt_multispace;
+use pagination::{fetch_clause, offset_clause, FetchClause, OffsetClause};
 use select::{limit_clause, nested_selection, order_clause, LimitClause, OrderClause,
              SelectStatement};
+use span::Span;
+
+pub use pagination::{FetchKeyword, FetchRowsOption, RowsNoun};
 
 #[derive(Clone, Debug, Hash, PartialEq, Deserialize, Serialize)]
 pub enum CompoundSelectOperator {
@@ -13,11 +17,27 @@ pub enum CompoundSelectOperator {
     Except,
 }
 
+impl fmt::Display for CompoundSelectOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CompoundSelectOperator::Union => write!(f, "UNION ALL"),
+            CompoundSelectOperator::DistinctUnion => write!(f, "UNION"),
+            CompoundSelectOperator::Intersect => write!(f, "INTERSECT"),
+            CompoundSelectOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Deserialize, Serialize)]
 pub struct CompoundSelectStatement {
-    pub selects: Vec<(Option<CompoundSelectOperator>, SelectStatement)>,
+    /// The operator joining each select to the previous one (`None` for the first), the
+    /// select itself, and its source span. The span is ignored by `PartialEq`/`Hash` (see
+    /// `Span`), so it can be left as `Span::default()` in hand-built test expectations.
+    pub selects: Vec<(Option<CompoundSelectOperator>, SelectStatement, Span)>,
     pub order: Option<OrderClause>,
     pub limit: Option<LimitClause>,
+    pub offset: Option<OffsetClause>,
+    pub fetch: Option<FetchClause>,
 }
 
 /// Parse compound operator
@@ -47,39 +67,86 @@ named!(compound_op<&[u8], CompoundSelectOperator>,
     )
 );
 
-/// Parse compound selection
-named!(pub compound_selection<&[u8], CompoundSelectStatement>,
-    complete!(do_parse!(
-        first_select: delimited!(opt!(tag!("(")), nested_selection, opt!(tag!(")"))) >>
-        other_selects: many1!(
-            complete!(
-                do_parse!(opt_multispace >>
-                       op: compound_op >>
-                       multispace >>
-                       opt!(tag!("(")) >>
-                       opt_multispace >>
-                       select: nested_selection >>
-                       opt_multispace >>
-                       opt!(tag!(")")) >>
-                       (Some(op), select)
-                )
-            )
-        ) >>
+/// A single `<op> (SELECT ...)` entry that follows the first select in a compound
+/// statement, with its span computed relative to `origin` (the input the whole
+/// `compound_selection` call started from).
+fn other_select<'a>(
+    i: &'a [u8],
+    origin: &'a [u8],
+) -> IResult<&'a [u8], (Option<CompoundSelectOperator>, SelectStatement, Span)> {
+    let (rest, op) = try_parse!(i, do_parse!(
         opt_multispace >>
-        order: opt!(order_clause) >>
-        limit: opt!(limit_clause) >>
-        ({
-            let mut v = vec![(None, first_select)];
-            v.extend(other_selects);
-
-            CompoundSelectStatement {
-                selects: v,
-                order: order,
-                limit: limit,
+        op: compound_op >>
+        multispace >>
+        opt!(tag!("(")) >>
+        opt_multispace >>
+        (op)
+    ));
+    // The span covers the select itself, not the operator or parentheses around it.
+    let start = rest;
+    let (rest, select) = try_parse!(rest, nested_selection);
+    let span = Span::from_offsets(origin, start, rest);
+    let (rest, _) = try_parse!(rest, pair!(opt_multispace, opt!(tag!(")"))));
+    IResult::Done(rest, (Some(op), select, span))
+}
+
+/// Parse compound selection
+pub fn compound_selection(i: &[u8]) -> IResult<&[u8], CompoundSelectStatement> {
+    let origin = i;
+    let first_start = i;
+
+    let (rest, first_select) = try_parse!(
+        i,
+        delimited!(opt!(tag!("(")), nested_selection, opt!(tag!(")")))
+    );
+    let first_span = Span::from_offsets(origin, first_start, rest);
+
+    let (rest, other_selects) = try_parse!(rest, many1!(complete!(apply!(other_select, origin))));
+
+    let (rest, _) = try_parse!(rest, opt_multispace);
+    let (rest, order) = try_parse!(rest, opt!(order_clause));
+    let (rest, limit) = try_parse!(rest, opt!(limit_clause));
+    let (rest, offset) = try_parse!(rest, opt!(preceded!(opt_multispace, offset_clause)));
+    let (rest, fetch) = try_parse!(rest, opt!(preceded!(opt_multispace, fetch_clause)));
+
+    let mut selects = vec![(None, first_select, first_span)];
+    selects.extend(other_selects);
+
+    IResult::Done(
+        rest,
+        CompoundSelectStatement {
+            selects: selects,
+            order: order,
+            limit: limit,
+            offset: offset,
+            fetch: fetch,
+        },
+    )
+}
+
+impl fmt::Display for CompoundSelectStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(ref op, ref select, _) in &self.selects {
+            if let Some(ref op) = *op {
+                write!(f, " {} ", op)?;
             }
-        })
-    ))
-);
+            write!(f, "{}", select)?;
+        }
+        if let Some(ref order) = self.order {
+            write!(f, " {}", order)?;
+        }
+        if let Some(ref limit) = self.limit {
+            write!(f, " {}", limit)?;
+        }
+        if let Some(ref offset) = self.offset {
+            write!(f, " {}", offset)?;
+        }
+        if let Some(ref fetch) = self.fetch {
+            write!(f, " {}", fetch)?;
+        }
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -113,11 +180,13 @@ mod tests {
         };
         let expected = CompoundSelectStatement {
             selects: vec![
-                (None, first_select),
-                (Some(CompoundSelectOperator::DistinctUnion), second_select),
+                (None, first_select, Span::default()),
+                (Some(CompoundSelectOperator::DistinctUnion), second_select, Span::default()),
             ],
             order: None,
             limit: None,
+            offset: None,
+            fetch: None,
         };
 
         assert_eq!(res.unwrap().1, expected);
@@ -158,12 +227,14 @@ mod tests {
 
         let expected = CompoundSelectStatement {
             selects: vec![
-                (None, first_select),
-                (Some(CompoundSelectOperator::DistinctUnion), second_select),
-                (Some(CompoundSelectOperator::DistinctUnion), third_select),
+                (None, first_select, Span::default()),
+                (Some(CompoundSelectOperator::DistinctUnion), second_select, Span::default()),
+                (Some(CompoundSelectOperator::DistinctUnion), third_select, Span::default()),
             ],
             order: None,
             limit: None,
+            offset: None,
+            fetch: None,
         };
 
         assert_eq!(res.unwrap().1, expected);
@@ -192,13 +263,82 @@ mod tests {
         };
         let expected = CompoundSelectStatement {
             selects: vec![
-                (None, first_select),
-                (Some(CompoundSelectOperator::Union), second_select),
+                (None, first_select, Span::default()),
+                (Some(CompoundSelectOperator::Union), second_select, Span::default()),
             ],
             order: None,
             limit: None,
+            offset: None,
+            fetch: None,
         };
 
         assert_eq!(res.unwrap().1, expected);
     }
+
+    #[test]
+    fn union_with_offset_and_fetch() {
+        let qstr = "SELECT id FROM Vote UNION SELECT id FROM Rating \
+                    OFFSET 5 ROWS FETCH NEXT 10 ROWS ONLY;";
+        let res = compound_selection(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(
+            parsed.offset,
+            Some(OffsetClause {
+                count: 5,
+                rows_noun: Some(RowsNoun::Rows),
+            })
+        );
+        assert_eq!(
+            parsed.fetch,
+            Some(FetchClause {
+                keyword: FetchKeyword::Next,
+                count: 10,
+                percent: false,
+                rows_noun: RowsNoun::Rows,
+                rows_option: FetchRowsOption::Only,
+            })
+        );
+    }
+
+    #[test]
+    fn union_with_offset_only() {
+        let qstr = "SELECT id FROM Vote UNION SELECT id FROM Rating OFFSET 3;";
+        let res = compound_selection(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(
+            parsed.offset,
+            Some(OffsetClause {
+                count: 3,
+                rows_noun: None,
+            })
+        );
+        assert_eq!(parsed.fetch, None);
+    }
+
+    #[test]
+    fn it_captures_spans_for_each_select() {
+        let qstr = "SELECT id FROM Vote UNION SELECT id FROM Rating;";
+        let parsed = compound_selection(qstr.as_bytes()).unwrap().1;
+
+        assert_eq!(parsed.selects.len(), 2);
+        let (_, _, first_span) = parsed.selects[0];
+        let (_, _, second_span) = parsed.selects[1];
+        assert_eq!(first_span.start, 0);
+        assert!(second_span.start > first_span.end);
+    }
+
+    #[test]
+    fn it_round_trips_offset_and_fetch_through_display() {
+        let qstr = "SELECT id FROM Vote UNION SELECT id FROM Rating \
+                    OFFSET 5 ROWS FETCH NEXT 10 ROWS ONLY";
+        let parsed = compound_selection(qstr.as_bytes()).unwrap().1;
+        let displayed = format!("{}", parsed);
+        let reparsed = compound_selection(displayed.as_bytes()).unwrap().1;
+        assert_eq!(parsed.offset, reparsed.offset);
+        assert_eq!(parsed.fetch, reparsed.fetch);
+    }
 }
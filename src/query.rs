@@ -0,0 +1,68 @@
+use std::fmt;
+
+use user_function::{creation_function, function_drop, show_functions, CreateFunctionStatement,
+                     DropFunctionStatement, ShowFunctionsStatement};
+
+/// The top-level SQL statements this slice of the crate can parse and dispatch to. The full
+/// crate also carries variants for `SELECT`/`INSERT`/`UPDATE`/etc., but those live outside this
+/// snapshot, so only the function-related statements are represented here.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub enum SqlQuery {
+    CreateFunction(CreateFunctionStatement),
+    DropFunction(DropFunctionStatement),
+    ShowFunctions(ShowFunctionsStatement),
+}
+
+impl fmt::Display for SqlQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SqlQuery::CreateFunction(ref stmt) => write!(f, "{}", stmt),
+            SqlQuery::DropFunction(ref stmt) => write!(f, "{}", stmt),
+            SqlQuery::ShowFunctions(ref stmt) => write!(f, "{}", stmt),
+        }
+    }
+}
+
+/// Parse any top-level statement known to this slice of the crate.
+named!(pub sql_query<&[u8], SqlQuery>,
+    alt_complete!(
+          map!(creation_function, SqlQuery::CreateFunction)
+        | map!(function_drop, SqlQuery::DropFunction)
+        | map!(show_functions, SqlQuery::ShowFunctions)
+    )
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_dispatches_create_function() {
+        let res = sql_query("CREATE FUNCTION add(x int) RETURN x+1;".as_bytes());
+        assert!(res.is_done());
+        match res.unwrap().1 {
+            SqlQuery::CreateFunction(stmt) => assert_eq!(stmt.name, "add"),
+            q => panic!("expected SqlQuery::CreateFunction, got {:?}", q),
+        }
+    }
+
+    #[test]
+    fn it_dispatches_drop_function() {
+        let res = sql_query("DROP FUNCTION add;".as_bytes());
+        assert!(res.is_done());
+        match res.unwrap().1 {
+            SqlQuery::DropFunction(stmt) => assert_eq!(stmt.name, "add"),
+            q => panic!("expected SqlQuery::DropFunction, got {:?}", q),
+        }
+    }
+
+    #[test]
+    fn it_dispatches_show_functions() {
+        let res = sql_query("SHOW FUNCTIONS;".as_bytes());
+        assert!(res.is_done());
+        match res.unwrap().1 {
+            SqlQuery::ShowFunctions(_) => {}
+            q => panic!("expected SqlQuery::ShowFunctions, got {:?}", q),
+        }
+    }
+}
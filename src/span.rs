@@ -0,0 +1,71 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A byte-offset range into the original query text that a parsed AST node was produced
+/// from, letting downstream consumers (error messages, linters, rewrite tools) map a node
+/// back to its source location without re-scanning the SQL text.
+///
+/// Spans are purely informational: two otherwise-equal nodes that differ only in their span
+/// still compare equal and hash the same (see the `PartialEq`/`Hash` impls below), so
+/// existing parser tests that build expected values by hand don't need to track positions.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// An empty span used wherever location information wasn't captured.
+    pub const UNKNOWN: Span = Span { start: 0, end: 0 };
+
+    pub fn new(start: usize, end: usize) -> Self {
+        Span {
+            start: start,
+            end: end,
+        }
+    }
+
+    /// Computes a span from two `nom` remaining-input slices of the same backing buffer:
+    /// `start` is the input as it stood when parsing of the node began, `end` is what
+    /// remained once it finished.
+    pub fn from_offsets(origin: &[u8], start: &[u8], end: &[u8]) -> Self {
+        Span::new(origin.len() - start.len(), origin.len() - end.len())
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Span) -> bool {
+        true
+    }
+}
+
+impl Eq for Span {}
+
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_are_always_equal() {
+        assert_eq!(Span::new(0, 5), Span::new(10, 20));
+        assert_eq!(Span::default(), Span::UNKNOWN);
+    }
+
+    #[test]
+    fn from_offsets_computes_byte_ranges() {
+        let origin = b"5 + 42";
+        let start = &origin[..];
+        let end = &origin[6..];
+        assert_eq!(Span::from_offsets(origin, start, end), Span::new(0, 6));
+    }
+}
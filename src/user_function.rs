@@ -0,0 +1,272 @@
+use std::fmt;
+use std::str;
+
+use nom::multispace;
+
+use common::{opt_multispace, sql_identifier, statement_terminator, Literal};
+use column::{type_identifier, SqlType};
+use arithmetic::{arithmetic_climb, arithmetic_expression, ArithmeticBase, ArithmeticExpression};
+use select::{nested_selection, SelectStatement};
+
+/// A call to a user-defined scalar function, e.g. `add(1, 2)`, usable anywhere an
+/// `ArithmeticBase` can appear.
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct FunctionCall {
+    pub name: String,
+    pub arguments: Vec<ArithmeticBase>,
+}
+
+impl fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, arg) in self.arguments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A single `name type [DEFAULT value]` parameter in a `CREATE FUNCTION` signature.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub struct FunctionParameter {
+    pub name: String,
+    pub sql_type: SqlType,
+    pub default: Option<Literal>,
+}
+
+/// The body of a user-defined function: either a plain arithmetic expression, or a
+/// parenthesized `SELECT` to run when the function is invoked.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub enum FunctionBody {
+    Expression(ArithmeticExpression),
+    Select(Box<SelectStatement>),
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+pub struct CreateFunctionStatement {
+    pub name: String,
+    pub params: Vec<FunctionParameter>,
+    pub body: FunctionBody,
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct DropFunctionStatement {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ShowFunctionsStatement;
+
+impl fmt::Display for FunctionParameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.sql_type)?;
+        if let Some(ref default) = self.default {
+            write!(f, " DEFAULT {}", default)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FunctionBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FunctionBody::Expression(ref e) => write!(f, "{}", e),
+            FunctionBody::Select(ref s) => write!(f, "({})", s),
+        }
+    }
+}
+
+impl fmt::Display for CreateFunctionStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CREATE FUNCTION {}(", self.name)?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", param)?;
+        }
+        write!(f, ") RETURN {}", self.body)
+    }
+}
+
+impl fmt::Display for DropFunctionStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DROP FUNCTION ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for ShowFunctionsStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SHOW FUNCTIONS")
+    }
+}
+
+/// Parse a user-defined function call, e.g. `add(1, 2)`.
+pub fn function_call(i: &[u8]) -> ::nom::IResult<&[u8], FunctionCall> {
+    complete!(
+        i,
+        do_parse!(
+            name: map_res!(sql_identifier, str::from_utf8) >>
+            opt_multispace >>
+            tag!("(") >>
+            opt_multispace >>
+            arguments: separated_list!(
+                delimited!(opt_multispace, tag!(","), opt_multispace),
+                call!(|arg| arithmetic_climb(arg, arg, 0))
+            ) >>
+            opt_multispace >>
+            tag!(")") >>
+            (FunctionCall {
+                name: String::from(name),
+                arguments: arguments,
+            })
+        )
+    )
+}
+
+named!(function_parameter<&[u8], FunctionParameter>,
+    complete!(do_parse!(
+        name: map_res!(sql_identifier, str::from_utf8) >>
+        multispace >>
+        sql_type: type_identifier >>
+        default: opt!(
+            preceded!(
+                delimited!(multispace, tag_no_case!("default"), multispace),
+                ::common::literal
+            )
+        ) >>
+        (FunctionParameter {
+            name: String::from(name),
+            sql_type: sql_type,
+            default: default,
+        })
+    ))
+);
+
+named!(function_parameter_list<&[u8], Vec<FunctionParameter>>,
+    delimited!(
+        tag!("("),
+        separated_list!(delimited!(opt_multispace, tag!(","), opt_multispace), function_parameter),
+        tag!(")")
+    )
+);
+
+named!(function_body<&[u8], FunctionBody>,
+    alt_complete!(
+          map!(
+              delimited!(
+                  pair!(tag!("("), opt_multispace),
+                  nested_selection,
+                  pair!(opt_multispace, tag!(")"))
+              ),
+              |s| FunctionBody::Select(Box::new(s))
+          )
+        | map!(arithmetic_expression, FunctionBody::Expression)
+    )
+);
+
+named!(pub creation_function<&[u8], CreateFunctionStatement>,
+    complete!(do_parse!(
+        tag_no_case!("create") >>
+        multispace >>
+        tag_no_case!("function") >>
+        multispace >>
+        name: map_res!(sql_identifier, str::from_utf8) >>
+        opt_multispace >>
+        params: function_parameter_list >>
+        multispace >>
+        tag_no_case!("return") >>
+        multispace >>
+        body: function_body >>
+        statement_terminator >>
+        (CreateFunctionStatement {
+            name: String::from(name),
+            params: params,
+            body: body,
+        })
+    ))
+);
+
+named!(pub function_drop<&[u8], DropFunctionStatement>,
+    complete!(do_parse!(
+        tag_no_case!("drop") >>
+        multispace >>
+        tag_no_case!("function") >>
+        multispace >>
+        if_exists: opt!(terminated!(tag_no_case!("if exists"), multispace)) >>
+        name: map_res!(sql_identifier, str::from_utf8) >>
+        statement_terminator >>
+        (DropFunctionStatement {
+            name: String::from(name),
+            if_exists: if_exists.is_some(),
+        })
+    ))
+);
+
+named!(pub show_functions<&[u8], ShowFunctionsStatement>,
+    complete!(do_parse!(
+        tag_no_case!("show") >>
+        multispace >>
+        tag_no_case!("functions") >>
+        statement_terminator >>
+        (ShowFunctionsStatement)
+    ))
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_function_calls() {
+        use arithmetic::ArithmeticBase::Scalar;
+
+        let res = function_call("add(1, 2)".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            FunctionCall {
+                name: String::from("add"),
+                arguments: vec![Scalar(1.into()), Scalar(2.into())],
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_create_function_with_expression_body() {
+        let qstr = "CREATE FUNCTION add(x int) RETURN x+1;";
+        let res = creation_function(qstr.as_bytes());
+        assert!(res.is_done());
+
+        let parsed = res.unwrap().1;
+        assert_eq!(parsed.name, "add");
+        assert_eq!(parsed.params.len(), 1);
+    }
+
+    #[test]
+    fn it_parses_drop_function() {
+        let res = function_drop("DROP FUNCTION IF EXISTS add;".as_bytes());
+        assert!(res.is_done());
+        assert_eq!(
+            res.unwrap().1,
+            DropFunctionStatement {
+                name: String::from("add"),
+                if_exists: true,
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_show_functions() {
+        let res = show_functions("SHOW FUNCTIONS;".as_bytes());
+        assert!(res.is_done());
+    }
+}